@@ -0,0 +1,90 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// A small, bounded least-recently-used cache of decoded image bytes, keyed by their
+/// absolute byte offset within the backing image. Entries are shared via `Arc<[u8]>` so
+/// cloned `EroFS` handles reuse the same decoded blocks instead of re-allocating them.
+#[derive(Debug)]
+pub(crate) struct BlockCache {
+    capacity: usize,
+    // Ordered least- to most-recently-used; linear scans are fine at the small capacities
+    // this cache is meant for (a handful of hot directory/inode blocks per lookup).
+    entries: Vec<(u64, Arc<[u8]>)>,
+}
+
+impl BlockCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: u64) -> Option<Arc<[u8]>> {
+        let pos = self.entries.iter().position(|(k, _)| *k == key)?;
+        let entry = self.entries.remove(pos);
+        let value = Arc::clone(&entry.1);
+        self.entries.push(entry);
+        Some(value)
+    }
+
+    pub(crate) fn insert(&mut self, key: u64, value: Arc<[u8]>) {
+        if let Some(pos) = self.entries.iter().position(|(k, _)| *k == key) {
+            self.entries.remove(pos);
+        } else if self.capacity != 0 && self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        if self.capacity != 0 {
+            self.entries.push((key, value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(byte: u8) -> Arc<[u8]> {
+        Arc::from(vec![byte])
+    }
+
+    fn keys(cache: &BlockCache) -> Vec<u64> {
+        cache.entries.iter().map(|(k, _)| *k).collect()
+    }
+
+    #[test]
+    fn evicts_least_recently_used_at_capacity() {
+        let mut cache = BlockCache::new(2);
+        cache.insert(1, block(1));
+        cache.insert(2, block(2));
+        cache.insert(3, block(3));
+
+        assert_eq!(keys(&cache), vec![2, 3]);
+        assert!(cache.get(1).is_none());
+    }
+
+    #[test]
+    fn reinserting_existing_key_bumps_recency_without_duplicating() {
+        let mut cache = BlockCache::new(2);
+        cache.insert(1, block(1));
+        cache.insert(2, block(2));
+        cache.insert(1, block(10));
+
+        assert_eq!(keys(&cache), vec![2, 1]);
+        assert_eq!(cache.get(1).unwrap()[0], 10);
+    }
+
+    #[test]
+    fn get_bumps_recency() {
+        let mut cache = BlockCache::new(2);
+        cache.insert(1, block(1));
+        cache.insert(2, block(2));
+
+        assert!(cache.get(1).is_some());
+        assert_eq!(keys(&cache), vec![2, 1]);
+
+        // 2 is now least-recently-used and should be the one evicted.
+        cache.insert(3, block(3));
+        assert_eq!(keys(&cache), vec![1, 3]);
+    }
+}
@@ -0,0 +1,178 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use crate::{Error, Result};
+
+const DIRENT_SIZE: usize = 12;
+
+/// The type of file a [`DirEntry`] refers to, as recorded in its `erofs_dirent.file_type`
+/// byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    Unknown,
+    RegularFile,
+    Directory,
+    CharDevice,
+    BlockDevice,
+    Fifo,
+    Socket,
+    Symlink,
+}
+
+impl FileType {
+    fn from_raw(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => FileType::Unknown,
+            1 => FileType::RegularFile,
+            2 => FileType::Directory,
+            3 => FileType::CharDevice,
+            4 => FileType::BlockDevice,
+            5 => FileType::Fifo,
+            6 => FileType::Socket,
+            7 => FileType::Symlink,
+            other => return Err(Error::InvalidDirentFileType(other)),
+        })
+    }
+
+    pub fn is_dir(self) -> bool {
+        matches!(self, FileType::Directory)
+    }
+
+    pub fn is_symlink(self) -> bool {
+        matches!(self, FileType::Symlink)
+    }
+}
+
+/// A single entry yielded while listing a directory's contents.
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub nid: u64,
+    pub name: String,
+    pub file_type: FileType,
+}
+
+struct RawDirent {
+    nid: u64,
+    nameoff: u16,
+    file_type: u8,
+}
+
+fn read_raw_dirent(block: &[u8], index: usize) -> Result<RawDirent> {
+    let off = index * DIRENT_SIZE;
+    let buf = block
+        .get(off..off + DIRENT_SIZE)
+        .ok_or_else(|| Error::CorruptedData("dirent record out of bounds".to_owned()))?;
+    let nid = buf[0..8]
+        .try_into()
+        .map_err(|_| Error::CorruptedData("failed to read dirent nid".to_owned()))?;
+    let nameoff = buf[8..10]
+        .try_into()
+        .map_err(|_| Error::CorruptedData("failed to read dirent nameoff".to_owned()))?;
+    Ok(RawDirent {
+        nid: u64::from_le_bytes(nid),
+        nameoff: u16::from_le_bytes(nameoff),
+        file_type: buf[10],
+    })
+}
+
+/// Parses every `erofs_dirent` record packed into a single directory data block.
+pub(crate) fn parse_block(block: &[u8]) -> Result<Vec<DirEntry>> {
+    if block.len() < DIRENT_SIZE {
+        return Ok(Vec::new());
+    }
+
+    let count = (read_raw_dirent(block, 0)?.nameoff as usize) / DIRENT_SIZE;
+    let mut entries = Vec::with_capacity(count);
+    for i in 0..count {
+        let raw = read_raw_dirent(block, i)?;
+        let name_end = if i + 1 < count {
+            read_raw_dirent(block, i + 1)?.nameoff as usize
+        } else {
+            block.len()
+        };
+        let name_bytes = block
+            .get(raw.nameoff as usize..name_end)
+            .ok_or_else(|| Error::CorruptedData("dirent name out of bounds".to_owned()))?;
+        let name_bytes = name_bytes
+            .split(|&b| b == 0)
+            .next()
+            .unwrap_or(name_bytes);
+        entries.push(DirEntry {
+            nid: raw.nid,
+            name: String::from_utf8_lossy(name_bytes).into_owned(),
+            file_type: FileType::from_raw(raw.file_type)?,
+        });
+    }
+    Ok(entries)
+}
+
+pub(crate) fn find_nodeid_by_name(name: &str, block: &[u8]) -> Result<Option<u64>> {
+    for entry in parse_block(block)? {
+        if entry.name == name {
+            return Ok(Some(entry.nid));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_dirent(block: &mut Vec<u8>, nid: u64, nameoff: u16, file_type: u8) {
+        block.extend_from_slice(&nid.to_le_bytes());
+        block.extend_from_slice(&nameoff.to_le_bytes());
+        block.push(file_type);
+        block.push(0); // reserved
+    }
+
+    #[test]
+    fn parses_multiple_entries() {
+        let mut block = Vec::new();
+        push_dirent(&mut block, 2, 3 * DIRENT_SIZE as u16, 2); // "a" -> Directory
+        push_dirent(&mut block, 3, 3 * DIRENT_SIZE as u16 + 1, 1); // "bb" -> RegularFile
+        push_dirent(&mut block, 4, 3 * DIRENT_SIZE as u16 + 3, 7); // "c" -> Symlink
+        block.extend_from_slice(b"abbc");
+
+        let entries = parse_block(&block).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].nid, 2);
+        assert_eq!(entries[0].name, "a");
+        assert_eq!(entries[0].file_type, FileType::Directory);
+        assert_eq!(entries[1].nid, 3);
+        assert_eq!(entries[1].name, "bb");
+        assert_eq!(entries[1].file_type, FileType::RegularFile);
+        assert_eq!(entries[2].nid, 4);
+        assert_eq!(entries[2].name, "c");
+        assert_eq!(entries[2].file_type, FileType::Symlink);
+    }
+
+    #[test]
+    fn truncated_block_yields_no_entries() {
+        let block = vec![0u8; DIRENT_SIZE - 1];
+        assert_eq!(parse_block(&block).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn corrupt_nameoff_past_block_end_errors() {
+        let mut block = Vec::new();
+        // nameoff implies a second dirent record that the block is too short to hold.
+        push_dirent(&mut block, 1, 2 * DIRENT_SIZE as u16 + 6, 1);
+        let result = parse_block(&block);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn find_nodeid_by_name_matches_and_misses() {
+        let mut block = Vec::new();
+        push_dirent(&mut block, 10, 2 * DIRENT_SIZE as u16, 1);
+        push_dirent(&mut block, 11, 2 * DIRENT_SIZE as u16 + 6, 1);
+        block.extend_from_slice(b"hello\0world");
+
+        assert_eq!(find_nodeid_by_name("hello", &block).unwrap(), Some(10));
+        assert_eq!(find_nodeid_by_name("world", &block).unwrap(), Some(11));
+        assert_eq!(find_nodeid_by_name("missing", &block).unwrap(), None);
+    }
+}
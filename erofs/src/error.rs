@@ -22,6 +22,8 @@ pub enum Error {
     NotSupported(String),
 
     CorruptedData(String),
+
+    TooManySymlinks,
 }
 
 impl fmt::Display for Error {
@@ -39,6 +41,7 @@ impl fmt::Display for Error {
             Error::OutOfRange(got, max) => write!(f, "out of range {} of {}", got, max),
             Error::NotSupported(msg) => write!(f, "{} not supported yet", msg),
             Error::CorruptedData(msg) => write!(f, "corrupted data: {}", msg),
+            Error::TooManySymlinks => write!(f, "too many levels of symbolic links"),
         }
     }
 }
@@ -1,25 +1,40 @@
-use alloc::{format, string::ToString, sync::Arc, vec, vec::Vec};
+use alloc::{
+    collections::VecDeque, format, string::String, string::ToString, sync::Arc, vec, vec::Vec,
+};
 
 #[cfg(feature = "std")]
 use memmap2::Mmap;
 
 #[cfg(feature = "std")]
-use std::path::{Component, Path};
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(feature = "std")]
+use std::sync::Mutex;
 
 use core::convert::TryInto;
 
+use crate::cache::BlockCache;
 use crate::dirent;
+pub use crate::dirent::{DirEntry, FileType};
 use crate::file::File;
 use crate::image::ReadAt;
 use crate::types::*;
+use crate::xattr;
+pub use crate::xattr::XattrLookup;
 use crate::{Error, Result};
 
+/// Maximum number of symlinks resolved while walking a single path, guarding against
+/// symlink loops.
+const MAX_SYMLINK_EXPANSIONS: usize = 40;
+
 #[derive(Debug)]
 pub struct EroFS<R: ReadAt> {
     reader: Arc<R>,
     image_size: u64,
     super_block: SuperBlock,
     block_size: usize,
+    #[cfg(feature = "std")]
+    cache: Option<Arc<Mutex<BlockCache>>>,
 }
 
 impl<R: ReadAt> Clone for EroFS<R> {
@@ -29,12 +44,38 @@ impl<R: ReadAt> Clone for EroFS<R> {
             image_size: self.image_size,
             super_block: self.super_block,
             block_size: self.block_size,
+            #[cfg(feature = "std")]
+            cache: self.cache.clone(),
         }
     }
 }
 
 impl<R: ReadAt> EroFS<R> {
     pub async fn from_image(reader: R, image_size: u64) -> Result<Self> {
+        Self::from_image_with_cache_capacity(reader, image_size, 0).await
+    }
+
+    /// Like [`EroFS::from_image`], but reads go through an in-memory LRU cache of decoded
+    /// blocks (keyed by their byte offset in the image) bounded to `cache_capacity` entries.
+    /// Cloned handles share the same cache, so hot directory/inode blocks are decoded once
+    /// per image rather than once per lookup.
+    #[cfg(feature = "std")]
+    pub async fn from_image_with_cache(
+        reader: R,
+        image_size: u64,
+        cache_capacity: usize,
+    ) -> Result<Self> {
+        Self::from_image_with_cache_capacity(reader, image_size, cache_capacity).await
+    }
+
+    async fn from_image_with_cache_capacity(
+        reader: R,
+        image_size: u64,
+        cache_capacity: usize,
+    ) -> Result<Self> {
+        #[cfg(not(feature = "std"))]
+        let _ = cache_capacity;
+
         let reader = Arc::new(reader);
         let mut sb_buf = vec![0u8; SuperBlock::size()];
         read_exact_at(
@@ -64,6 +105,9 @@ impl<R: ReadAt> EroFS<R> {
             image_size,
             super_block,
             block_size: (1u64 << super_block.blk_size_bits) as usize,
+            #[cfg(feature = "std")]
+            cache: (cache_capacity > 0)
+                .then(|| Arc::new(Mutex::new(BlockCache::new(cache_capacity)))),
         })
     }
 
@@ -90,6 +134,16 @@ impl<R: ReadAt> EroFS<R> {
         self.open_inode_file(inode).await
     }
 
+    /// Like [`EroFS::open_path`], but if the final path component is itself a symlink,
+    /// returns the symlink inode rather than following it.
+    pub async fn open_path_nofollow(&self, path: &str) -> Result<File<R>> {
+        let inode = self
+            .get_path_inode_str_nofollow(path)
+            .await?
+            .ok_or_else(|| Error::PathNotFound(path.to_string()))?;
+        self.open_inode_file_nofollow(inode).await
+    }
+
     #[cfg(feature = "std")]
     pub async fn open<P: AsRef<Path>>(&self, path: P) -> Result<File<R>> {
         let inode = self
@@ -114,6 +168,52 @@ impl<R: ReadAt> EroFS<R> {
         Ok(File::new(inode, self.clone()))
     }
 
+    /// Like [`EroFS::open_inode_file`], but also accepts a symlink's own inode (as opposed
+    /// to what it points to) instead of requiring a regular file. Used by
+    /// [`EroFS::open_path_nofollow`], where the final component may legitimately be a link.
+    async fn open_inode_file_nofollow(&self, inode: Inode) -> Result<File<R>> {
+        if inode.is_dir() {
+            return Err(Error::NotAFile(format!(
+                "inode {} is a directory",
+                inode.id()
+            )));
+        }
+        Ok(File::new(inode, self.clone()))
+    }
+
+    /// Lists the contents of `inode`, returning an entry for everything it contains
+    /// (including the `.` and `..` self/parent entries).
+    pub async fn read_dir(&self, inode: &Inode) -> Result<Vec<DirEntry>> {
+        if !inode.is_dir() {
+            return Err(Error::NotADirectory(format!(
+                "inode {} is not a directory",
+                inode.id()
+            )));
+        }
+
+        let block_count = inode.data_size().div_ceil(self.block_size);
+        let mut entries = Vec::new();
+        for i in 0..block_count {
+            let block = self.get_inode_block(inode, i * self.block_size).await?;
+            entries.extend(dirent::parse_block(&block)?);
+        }
+        Ok(entries)
+    }
+
+    pub async fn open_dir(&self, path: &str) -> Result<Inode> {
+        let inode = self
+            .get_path_inode_str(path)
+            .await?
+            .ok_or_else(|| Error::PathNotFound(path.to_string()))?;
+        if !inode.is_dir() {
+            return Err(Error::NotADirectory(format!(
+                "inode {} is not a directory",
+                inode.id()
+            )));
+        }
+        Ok(inode)
+    }
+
     pub async fn get_inode(&self, nid: u64) -> Result<Inode> {
         let offset = self.get_inode_offset(nid);
         let mut layout_buf = [0u8; 2];
@@ -126,24 +226,10 @@ impl<R: ReadAt> EroFS<R> {
         .await?;
         let layout = u16::from_le_bytes(layout_buf);
         if Inode::is_compact_format(layout) {
-            let mut inode_buf = vec![0u8; InodeCompact::size()];
-            read_exact_at(
-                self.reader.as_ref(),
-                self.image_size,
-                offset,
-                &mut inode_buf,
-            )
-            .await?;
+            let inode_buf = self.fetch_bytes(offset, InodeCompact::size()).await?;
             Ok(Inode::Compact((nid, InodeCompact::read_from(&inode_buf)?)))
         } else {
-            let mut inode_buf = vec![0u8; InodeExtended::size()];
-            read_exact_at(
-                self.reader.as_ref(),
-                self.image_size,
-                offset,
-                &mut inode_buf,
-            )
-            .await?;
+            let inode_buf = self.fetch_bytes(offset, InodeExtended::size()).await?;
             Ok(Inode::Extended((
                 nid,
                 InodeExtended::read_from(&inode_buf)?,
@@ -176,7 +262,7 @@ impl<R: ReadAt> EroFS<R> {
         Ok(written)
     }
 
-    pub(crate) async fn get_inode_block(&self, inode: &Inode, offset: usize) -> Result<Vec<u8>> {
+    pub(crate) async fn get_inode_block(&self, inode: &Inode, offset: usize) -> Result<Arc<[u8]>> {
         match inode.layout()? {
             Layout::FlatPlain => {
                 let block_count = inode.data_size().div_ceil(self.block_size);
@@ -190,9 +276,7 @@ impl<R: ReadAt> EroFS<R> {
                     .ok_or_else(|| Error::OutOfBounds("inode block offset overflow".to_string()))?;
                 let len =
                     (inode.data_size() - (block_index * self.block_size)).min(self.block_size);
-                let mut out = vec![0u8; len];
-                read_exact_at(self.reader.as_ref(), self.image_size, start, &mut out).await?;
-                Ok(out)
+                self.fetch_bytes(start, len).await
             }
             Layout::FlatInline => {
                 let block_count = inode.data_size().div_ceil(self.block_size);
@@ -209,9 +293,7 @@ impl<R: ReadAt> EroFS<R> {
                             Error::OutOfBounds("inode tail offset overflow".to_string())
                         })?;
                     let len = inode.data_size() % self.block_size;
-                    let mut out = vec![0u8; len];
-                    read_exact_at(self.reader.as_ref(), self.image_size, start, &mut out).await?;
-                    return Ok(out);
+                    return self.fetch_bytes(start, len).await;
                 }
 
                 let start = self
@@ -220,9 +302,7 @@ impl<R: ReadAt> EroFS<R> {
                     .ok_or_else(|| Error::OutOfBounds("inode block offset overflow".to_string()))?;
                 let len =
                     (inode.data_size() - (block_index * self.block_size)).min(self.block_size);
-                let mut out = vec![0u8; len];
-                read_exact_at(self.reader.as_ref(), self.image_size, start, &mut out).await?;
-                Ok(out)
+                self.fetch_bytes(start, len).await
             }
             Layout::CompressedFull | Layout::CompressedCompact => {
                 Err(Error::NotSupported("compressed compact layout".to_string()))
@@ -278,35 +358,56 @@ impl<R: ReadAt> EroFS<R> {
                     self.block_size
                 };
                 let start = self.block_offset(chunk_addr as u32 + chunk_fixed as u32);
-                let mut out = vec![0u8; len];
-                read_exact_at(self.reader.as_ref(), self.image_size, start, &mut out).await?;
-                Ok(out)
+                self.fetch_bytes(start, len).await
             }
         }
     }
 
-    #[cfg(feature = "std")]
-    pub async fn get_path_inode(&self, path: &Path) -> Result<Option<Inode>> {
-        let mut nid = self.super_block.root_nid as u64;
-        'outer: for part in path.components() {
-            if part == Component::RootDir {
-                continue;
-            }
-            let inode = self.get_inode(nid).await?;
-            let block_count = inode.data_size().div_ceil(self.block_size);
-            if block_count == 0 {
-                return Ok(None);
-            }
-            for i in 0..block_count {
-                let block = self.get_inode_block(&inode, i * self.block_size).await?;
-                if let Some(found_nid) = dirent::find_nodeid_by_name(part.as_os_str(), &block)? {
-                    nid = found_nid;
-                    continue 'outer;
+    /// Reads `len` bytes at absolute image offset `start`, consulting the block cache
+    /// first (if one was configured via [`EroFS::from_image_with_cache`]) and populating
+    /// it on a miss.
+    async fn fetch_bytes(&self, start: u64, len: usize) -> Result<Arc<[u8]>> {
+        #[cfg(feature = "std")]
+        if let Some(cache) = &self.cache {
+            if let Some(cached) = cache.lock().unwrap().get(start) {
+                if cached.len() == len {
+                    return Ok(cached);
                 }
             }
-            return Ok(None);
         }
-        Ok(Some(self.get_inode(nid).await?))
+
+        let mut out = vec![0u8; len];
+        read_exact_at(self.reader.as_ref(), self.image_size, start, &mut out).await?;
+        let block: Arc<[u8]> = Arc::from(out);
+
+        #[cfg(feature = "std")]
+        if let Some(cache) = &self.cache {
+            cache.lock().unwrap().insert(start, Arc::clone(&block));
+        }
+
+        Ok(block)
+    }
+
+    #[cfg(feature = "std")]
+    pub async fn get_path_inode(&self, path: &Path) -> Result<Option<Inode>> {
+        self.resolve_path(Self::path_to_components(path), true)
+            .await
+    }
+
+    /// Like [`EroFS::get_path_inode`], but if the final path component is itself a
+    /// symlink, returns the symlink inode rather than following it.
+    #[cfg(feature = "std")]
+    pub async fn get_path_inode_nofollow(&self, path: &Path) -> Result<Option<Inode>> {
+        self.resolve_path(Self::path_to_components(path), false)
+            .await
+    }
+
+    #[cfg(feature = "std")]
+    fn path_to_components(path: &Path) -> VecDeque<String> {
+        path.components()
+            .filter(|part| *part != Component::RootDir)
+            .map(|part| part.as_os_str().to_string_lossy().into_owned())
+            .collect()
     }
 
     #[cfg(feature = "std")]
@@ -314,30 +415,209 @@ impl<R: ReadAt> EroFS<R> {
         self.get_path_inode(Path::new(path)).await
     }
 
+    #[cfg(feature = "std")]
+    pub async fn get_path_inode_str_nofollow(&self, path: &str) -> Result<Option<Inode>> {
+        self.get_path_inode_nofollow(Path::new(path)).await
+    }
+
     #[cfg(not(feature = "std"))]
     pub async fn get_path_inode_str(&self, path: &str) -> Result<Option<Inode>> {
+        self.resolve_path(Self::str_to_components(path), true)
+            .await
+    }
+
+    /// Like [`EroFS::get_path_inode_str`], but if the final path component is itself a
+    /// symlink, returns the symlink inode rather than following it.
+    #[cfg(not(feature = "std"))]
+    pub async fn get_path_inode_str_nofollow(&self, path: &str) -> Result<Option<Inode>> {
+        self.resolve_path(Self::str_to_components(path), false)
+            .await
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn str_to_components(path: &str) -> VecDeque<String> {
+        path.split('/')
+            .filter(|part| !part.is_empty() && *part != ".")
+            .map(|part| part.to_string())
+            .collect()
+    }
+
+    /// Resolves a queue of path components into an inode, following symlinks found along
+    /// the way (and in the final component too, unless `follow_last` is `false`).
+    ///
+    /// Symlink targets are spliced back into the front of the component queue so the rest
+    /// of the original path is resolved relative to wherever the link points; an absolute
+    /// target restarts resolution from the root. `MAX_SYMLINK_EXPANSIONS` bounds the number
+    /// of links followed to guard against loops.
+    async fn resolve_path(
+        &self,
+        mut components: VecDeque<String>,
+        follow_last: bool,
+    ) -> Result<Option<Inode>> {
         let mut nid = self.super_block.root_nid as u64;
-        'outer: for part in path.split('/') {
-            if part.is_empty() || part == "." {
-                continue;
-            }
+        let mut expansions = 0usize;
+
+        while let Some(part) = components.pop_front() {
             let inode = self.get_inode(nid).await?;
             let block_count = inode.data_size().div_ceil(self.block_size);
-            if block_count == 0 {
-                return Ok(None);
-            }
+            let mut found_nid = None;
             for i in 0..block_count {
                 let block = self.get_inode_block(&inode, i * self.block_size).await?;
-                if let Some(found_nid) = dirent::find_nodeid_by_name(part, &block)? {
-                    nid = found_nid;
-                    continue 'outer;
+                if let Some(nid) = dirent::find_nodeid_by_name(&part, &block)? {
+                    found_nid = Some(nid);
+                    break;
                 }
             }
-            return Ok(None);
+            let found_nid = match found_nid {
+                Some(nid) => nid,
+                None => return Ok(None),
+            };
+
+            if components.is_empty() && !follow_last {
+                nid = found_nid;
+                break;
+            }
+
+            let found_inode = self.get_inode(found_nid).await?;
+            if !found_inode.is_symlink() {
+                nid = found_nid;
+                continue;
+            }
+
+            bump_symlink_expansions(&mut expansions)?;
+
+            let mut target = vec![0u8; found_inode.data_size()];
+            self.read_inode_range(&found_inode, 0, &mut target).await?;
+            let target = core::str::from_utf8(&target)
+                .map_err(|_| Error::CorruptedData("symlink target is not valid utf-8".to_string()))?;
+
+            if splice_symlink_target(target, &mut components) {
+                nid = self.super_block.root_nid as u64;
+            }
         }
+
         Ok(Some(self.get_inode(nid).await?))
     }
 
+    /// Depth-first-walks the whole tree starting at the superblock root, yielding every
+    /// entry it finds (except the `.`/`..` self/parent entries) alongside its path.
+    #[cfg(feature = "std")]
+    pub async fn walk(&self) -> Result<Vec<(PathBuf, Inode)>> {
+        let root = self.get_inode(self.super_block.root_nid as u64).await?;
+        self.walk_from(root).await
+    }
+
+    #[cfg(feature = "std")]
+    pub async fn walk_from(&self, inode: Inode) -> Result<Vec<(PathBuf, Inode)>> {
+        let mut out = Vec::new();
+        let mut stack = vec![(PathBuf::new(), inode)];
+        while let Some((prefix, dir)) = stack.pop() {
+            for entry in self.read_dir(&dir).await? {
+                if entry.name == "." || entry.name == ".." {
+                    continue;
+                }
+                let path = prefix.join(&entry.name);
+                let child = self.get_inode(entry.nid).await?;
+                if entry.file_type.is_dir() {
+                    stack.push((path.clone(), child.clone()));
+                }
+                out.push((path, child));
+            }
+        }
+        Ok(out)
+    }
+
+    /// `no_std` counterpart of [`EroFS::walk`], using forward-slash-joined `String` paths
+    /// instead of `std::path::PathBuf`.
+    #[cfg(not(feature = "std"))]
+    pub async fn walk(&self) -> Result<Vec<(String, Inode)>> {
+        let root = self.get_inode(self.super_block.root_nid as u64).await?;
+        self.walk_from(root).await
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub async fn walk_from(&self, inode: Inode) -> Result<Vec<(String, Inode)>> {
+        let mut out = Vec::new();
+        let mut stack = vec![(String::new(), inode)];
+        while let Some((prefix, dir)) = stack.pop() {
+            for entry in self.read_dir(&dir).await? {
+                if entry.name == "." || entry.name == ".." {
+                    continue;
+                }
+                let path = if prefix.is_empty() {
+                    entry.name.clone()
+                } else {
+                    format!("{}/{}", prefix, entry.name)
+                };
+                let child = self.get_inode(entry.nid).await?;
+                if entry.file_type.is_dir() {
+                    stack.push((path.clone(), child.clone()));
+                }
+                out.push((path, child));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Reads `inode`'s raw inline xattr region (the bytes [`xattr::parse_region`] and
+    /// [`xattr::shared_count`] operate on), or an empty region if it has none.
+    async fn read_xattr_region(&self, inode: &Inode) -> Result<Vec<u8>> {
+        let xattr_size = inode.xattr_size();
+        if xattr_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let offset = self
+            .get_inode_offset(inode.id())
+            .checked_add(inode.size() as u64)
+            .ok_or_else(|| Error::OutOfBounds("xattr region offset overflow".to_string()))?;
+        let mut region = vec![0u8; xattr_size];
+        read_exact_at(self.reader.as_ref(), self.image_size, offset, &mut region).await?;
+        Ok(region)
+    }
+
+    /// Lists the extended attributes stored inline after `inode`'s fixed header, returning
+    /// each attribute's fully-qualified name (namespace prefix + suffix) and raw value.
+    ///
+    /// Does not resolve shared xattrs (attributes deduplicated into the image-wide shared
+    /// xattr table): only attributes stored inline on `inode` itself are returned. This is
+    /// a real gap for the Android/container use case this API targets, since
+    /// `security.selinux` and capability xattrs are commonly shared rather than inline. Use
+    /// [`EroFS::get_xattr`] when looking up a single known name, since it can at least tell
+    /// you when a miss is ambiguous rather than a confirmed absence.
+    pub async fn list_xattrs(&self, inode: &Inode) -> Result<Vec<(String, Vec<u8>)>> {
+        let region = self.read_xattr_region(inode).await?;
+        Ok(xattr::parse_region(&region)?
+            .into_iter()
+            .map(|raw| (raw.full_name(), raw.value))
+            .collect())
+    }
+
+    /// Looks up a single extended attribute by its fully-qualified name (e.g.
+    /// `security.selinux`).
+    ///
+    /// Unlike [`EroFS::list_xattrs`], this distinguishes a confirmed [`XattrLookup::Absent`]
+    /// from [`XattrLookup::Unresolved`]: `inode` may reference the name in the image-wide
+    /// shared xattr table, which erofs-rs does not yet resolve, so a miss against the inline
+    /// entries alone can't be reported as a genuine absence.
+    pub async fn get_xattr(&self, inode: &Inode, name: &str) -> Result<XattrLookup> {
+        let region = self.read_xattr_region(inode).await?;
+        let entries = xattr::parse_region(&region)?;
+        if let Some(value) = entries
+            .into_iter()
+            .find(|raw| raw.full_name() == name)
+            .map(|raw| raw.value)
+        {
+            return Ok(XattrLookup::Found(value));
+        }
+
+        if xattr::shared_count(&region) > 0 {
+            Ok(XattrLookup::Unresolved)
+        } else {
+            Ok(XattrLookup::Absent)
+        }
+    }
+
     fn get_inode_offset(&self, nid: u64) -> u64 {
         self.block_offset(self.super_block.meta_blk_addr) + (nid * InodeCompact::size() as u64)
     }
@@ -374,3 +654,81 @@ async fn read_exact_at<R: ReadAt + ?Sized>(
     }
     Ok(())
 }
+
+/// Splices a resolved symlink `target` back onto the front of the remaining path
+/// `components`, so the rest of the original path resolves relative to wherever the link
+/// points. Returns `true` if `target` is absolute, meaning resolution must restart at the
+/// filesystem root rather than continuing from the symlink's parent directory.
+fn splice_symlink_target(target: &str, components: &mut VecDeque<String>) -> bool {
+    for part in target.trim_matches('/').rsplit('/').filter(|p| !p.is_empty()) {
+        components.push_front(part.to_string());
+    }
+    target.starts_with('/')
+}
+
+/// Counts one more symlink expansion against `MAX_SYMLINK_EXPANSIONS`, erroring once the
+/// budget guarding against symlink loops is exhausted.
+fn bump_symlink_expansions(expansions: &mut usize) -> Result<()> {
+    *expansions += 1;
+    if *expansions > MAX_SYMLINK_EXPANSIONS {
+        return Err(Error::TooManySymlinks);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `resolve_path`'s on-disk decoding (superblock/inode parsing) lives in the `types`/
+    // `image` modules, which aren't part of this checkout, so it can't be exercised
+    // end-to-end here. These tests instead cover the two pieces of `resolve_path`'s own
+    // logic that are self-contained and, per `64e1e01`, have already proved fragile in
+    // practice: splicing a symlink target back onto the remaining path, and the
+    // `MAX_SYMLINK_EXPANSIONS` loop guard.
+
+    fn components(parts: &[&str]) -> VecDeque<String> {
+        parts.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn relative_symlink_target_is_spliced_in_front_without_root_restart() {
+        let mut remaining = components(&["d"]);
+        let restart_at_root = splice_symlink_target("b/c", &mut remaining);
+
+        assert!(!restart_at_root);
+        assert_eq!(remaining, components(&["b", "c", "d"]));
+    }
+
+    #[test]
+    fn absolute_symlink_target_is_spliced_and_requests_root_restart() {
+        let mut remaining = components(&["d"]);
+        let restart_at_root = splice_symlink_target("/b/c", &mut remaining);
+
+        assert!(restart_at_root);
+        assert_eq!(remaining, components(&["b", "c", "d"]));
+    }
+
+    #[test]
+    fn symlink_target_with_redundant_slashes_is_normalized() {
+        let mut remaining = VecDeque::new();
+        splice_symlink_target("//b//c//", &mut remaining);
+
+        assert_eq!(remaining, components(&["b", "c"]));
+    }
+
+    #[test]
+    fn expansions_within_budget_succeed() {
+        let mut expansions = 0usize;
+        for _ in 0..MAX_SYMLINK_EXPANSIONS {
+            bump_symlink_expansions(&mut expansions).unwrap();
+        }
+    }
+
+    #[test]
+    fn expansions_past_budget_trip_too_many_symlinks() {
+        let mut expansions = MAX_SYMLINK_EXPANSIONS;
+        let err = bump_symlink_expansions(&mut expansions).unwrap_err();
+        assert!(matches!(err, Error::TooManySymlinks));
+    }
+}
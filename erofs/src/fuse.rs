@@ -0,0 +1,224 @@
+//! A [`fuser`](https://docs.rs/fuser) low-level FUSE adapter for [`EroFS`], gated behind the
+//! `fuse` feature. This lets an EROFS image (e.g. an Android or container rootfs) be mounted
+//! directly for inspection without extracting it first.
+
+use std::ffi::OsStr;
+use std::time::Duration;
+
+use fuser::{
+    FileAttr, FileType as FuseFileType, Filesystem, ReplyAttr, ReplyData, ReplyDirectory,
+    ReplyEntry, Request,
+};
+
+use crate::dirent::FileType;
+use crate::filesystem::EroFS;
+use crate::image::ReadAt;
+use crate::types::Inode;
+use crate::Error;
+
+const TTL: Duration = Duration::from_secs(1);
+const ROOT_INO: u64 = 1;
+
+/// Translates an [`Error`] into the errno `fuser` expects a failed callback to reply with.
+fn error_to_errno(err: &Error) -> i32 {
+    match err {
+        Error::PathNotFound(_) => libc::ENOENT,
+        Error::NotADirectory(_) => libc::ENOTDIR,
+        Error::NotAFile(_) => libc::EISDIR,
+        Error::NotSupported(_) => libc::ENOSYS,
+        Error::OutOfBounds(_) | Error::OutOfRange(_, _) => libc::ERANGE,
+        _ => libc::EIO,
+    }
+}
+
+fn file_type_to_fuse(file_type: FileType) -> FuseFileType {
+    match file_type {
+        FileType::Directory => FuseFileType::Directory,
+        FileType::Symlink => FuseFileType::Symlink,
+        FileType::CharDevice => FuseFileType::CharDevice,
+        FileType::BlockDevice => FuseFileType::BlockDevice,
+        FileType::Fifo => FuseFileType::NamedPipe,
+        FileType::Socket => FuseFileType::Socket,
+        FileType::RegularFile | FileType::Unknown => FuseFileType::RegularFile,
+    }
+}
+
+fn attr_for(ino: u64, inode: &Inode) -> FileAttr {
+    let kind = if inode.is_dir() {
+        FuseFileType::Directory
+    } else if inode.is_symlink() {
+        FuseFileType::Symlink
+    } else {
+        FuseFileType::RegularFile
+    };
+    FileAttr {
+        ino,
+        size: inode.data_size() as u64,
+        blocks: 0,
+        atime: std::time::UNIX_EPOCH,
+        mtime: std::time::UNIX_EPOCH,
+        ctime: std::time::UNIX_EPOCH,
+        crtime: std::time::UNIX_EPOCH,
+        kind,
+        perm: 0o444,
+        nlink: 1,
+        uid: 0,
+        gid: 0,
+        rdev: 0,
+        blksize: 512,
+        flags: 0,
+    }
+}
+
+/// Wraps an [`EroFS`] so it can be mounted with `fuser`'s low-level session.
+///
+/// FUSE reserves inode `1` for the mount root, but EROFS's `root_nid` can be any value, and
+/// nid `0` is reserved so it's never a real inode's nid. That makes `ino = nid + 1` (with the
+/// superblock's `root_nid` special-cased to `ROOT_INO`) a true bijection: no real nid is `0`,
+/// so no non-root nid can ever collide with `ROOT_INO` under the `+1` shift.
+pub struct EroFsFuse<R: ReadAt> {
+    fs: EroFS<R>,
+}
+
+impl<R: ReadAt> EroFsFuse<R> {
+    pub fn new(fs: EroFS<R>) -> Self {
+        Self { fs }
+    }
+
+    fn ino_to_nid(&self, ino: u64) -> u64 {
+        if ino == ROOT_INO {
+            self.fs.super_block().root_nid as u64
+        } else {
+            ino - 1
+        }
+    }
+
+    fn nid_to_ino(&self, nid: u64) -> u64 {
+        if nid == self.fs.super_block().root_nid as u64 {
+            ROOT_INO
+        } else {
+            nid + 1
+        }
+    }
+
+    /// Drives an `EroFS` future to completion on the calling FUSE worker thread.
+    fn block_on<F: core::future::Future>(&self, fut: F) -> F::Output {
+        futures::executor::block_on(fut)
+    }
+}
+
+impl<R: ReadAt> Filesystem for EroFsFuse<R> {
+    fn lookup(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEntry) {
+        let parent_nid = self.ino_to_nid(parent);
+        let name = match name.to_str() {
+            Some(name) => name,
+            None => return reply.error(libc::EINVAL),
+        };
+        let result = self.block_on(async {
+            let parent_inode = self.fs.get_inode(parent_nid).await?;
+            for entry in self.fs.read_dir(&parent_inode).await? {
+                if entry.name == name {
+                    return self.fs.get_inode(entry.nid).await.map(Some);
+                }
+            }
+            Ok(None)
+        });
+        match result {
+            Ok(Some(inode)) => {
+                let ino = self.nid_to_ino(inode.id());
+                reply.entry(&TTL, &attr_for(ino, &inode), 0)
+            }
+            Ok(None) => reply.error(libc::ENOENT),
+            Err(err) => reply.error(error_to_errno(&err)),
+        }
+    }
+
+    fn getattr(&mut self, _req: &Request<'_>, ino: u64, reply: ReplyAttr) {
+        let nid = self.ino_to_nid(ino);
+        match self.block_on(self.fs.get_inode(nid)) {
+            Ok(inode) => reply.attr(&TTL, &attr_for(ino, &inode)),
+            Err(err) => reply.error(error_to_errno(&err)),
+        }
+    }
+
+    fn read(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyData,
+    ) {
+        let nid = self.ino_to_nid(ino);
+        let result = self.block_on(async {
+            let inode = self.fs.get_inode(nid).await?;
+            let mut buf = vec![0u8; size as usize];
+            let n = self
+                .fs
+                .read_inode_range(&inode, offset as usize, &mut buf)
+                .await?;
+            buf.truncate(n);
+            Ok::<_, Error>(buf)
+        });
+        match result {
+            Ok(buf) => reply.data(&buf),
+            Err(err) => reply.error(error_to_errno(&err)),
+        }
+    }
+
+    fn readlink(&mut self, _req: &Request<'_>, ino: u64, reply: fuser::ReplyData) {
+        let nid = self.ino_to_nid(ino);
+        let result = self.block_on(async {
+            let inode = self.fs.get_inode(nid).await?;
+            let mut buf = vec![0u8; inode.data_size()];
+            self.fs.read_inode_range(&inode, 0, &mut buf).await?;
+            Ok::<_, Error>(buf)
+        });
+        match result {
+            Ok(buf) => reply.data(&buf),
+            Err(err) => reply.error(error_to_errno(&err)),
+        }
+    }
+
+    fn open(&mut self, _req: &Request<'_>, _ino: u64, _flags: i32, reply: fuser::ReplyOpen) {
+        reply.opened(0, 0);
+    }
+
+    fn opendir(&mut self, _req: &Request<'_>, _ino: u64, _flags: i32, reply: fuser::ReplyOpen) {
+        reply.opened(0, 0);
+    }
+
+    fn readdir(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        mut reply: ReplyDirectory,
+    ) {
+        let nid = self.ino_to_nid(ino);
+        let result = self.block_on(async {
+            let inode = self.fs.get_inode(nid).await?;
+            self.fs.read_dir(&inode).await
+        });
+        let entries = match result {
+            Ok(entries) => entries,
+            Err(err) => return reply.error(error_to_errno(&err)),
+        };
+        for (i, entry) in entries.into_iter().enumerate().skip(offset as usize) {
+            let full = reply.add(
+                self.nid_to_ino(entry.nid),
+                (i + 1) as i64,
+                file_type_to_fuse(entry.file_type),
+                &entry.name,
+            );
+            if full {
+                break;
+            }
+        }
+        reply.ok();
+    }
+}
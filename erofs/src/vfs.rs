@@ -0,0 +1,58 @@
+use alloc::vec::Vec;
+
+use crate::dirent::DirEntry;
+use crate::filesystem::EroFS;
+use crate::image::ReadAt;
+use crate::types::Inode;
+use crate::Result;
+
+/// A minimal, backend-agnostic read-only filesystem trait.
+///
+/// This lets callers (VFS layers, test harnesses, FUSE adapters, ...) depend on a single
+/// abstraction instead of [`EroFS`] directly, so other read-only backends can be swapped
+/// in without touching call sites.
+pub trait ReadOnlyFs {
+    type Inode;
+    type DirEntry;
+
+    /// Returns the root inode of the filesystem.
+    async fn root(&self) -> Result<Self::Inode>;
+
+    /// Resolves a `/`-separated path to its inode, if it exists.
+    async fn lookup(&self, path: &str) -> Result<Option<Self::Inode>>;
+
+    /// Lists the entries contained in `inode`.
+    async fn read_dir(&self, inode: &Self::Inode) -> Result<Vec<Self::DirEntry>>;
+
+    /// Reads up to `buf.len()` bytes of `inode`'s data starting at `offset`, returning the
+    /// number of bytes actually read.
+    async fn read_at(&self, inode: &Self::Inode, offset: usize, buf: &mut [u8]) -> Result<usize>;
+
+    /// Returns the metadata (inode) for `inode`, re-reading it from the backing image.
+    async fn metadata(&self, inode: &Self::Inode) -> Result<Self::Inode>;
+}
+
+impl<R: ReadAt> ReadOnlyFs for EroFS<R> {
+    type Inode = Inode;
+    type DirEntry = DirEntry;
+
+    async fn root(&self) -> Result<Inode> {
+        self.get_inode(self.super_block().root_nid as u64).await
+    }
+
+    async fn lookup(&self, path: &str) -> Result<Option<Inode>> {
+        self.get_path_inode_str(path).await
+    }
+
+    async fn read_dir(&self, inode: &Inode) -> Result<Vec<DirEntry>> {
+        EroFS::read_dir(self, inode).await
+    }
+
+    async fn read_at(&self, inode: &Inode, offset: usize, buf: &mut [u8]) -> Result<usize> {
+        self.read_inode_range(inode, offset, buf).await
+    }
+
+    async fn metadata(&self, inode: &Inode) -> Result<Inode> {
+        self.get_inode(inode.id()).await
+    }
+}
@@ -0,0 +1,184 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use crate::{Error, Result};
+
+/// Size of `erofs_xattr_ibody_header`: a 4-byte `name_filter`, a 1-byte `shared_count`,
+/// and 7 reserved bytes, before `h_shared_xattrs[]` starts.
+const HEADER_BASE_SIZE: usize = 12;
+const ENTRY_HEADER_SIZE: usize = 4;
+
+/// Maps an inline xattr entry's `name_index` onto the namespace prefix its suffix is
+/// relative to (e.g. `security.selinux` for index `6` and suffix `selinux`). EROFS reuses
+/// the ext2/ext4/f2fs xattr index numbering.
+fn prefix_for_index(index: u8) -> String {
+    match index {
+        1 => "user.".into(),
+        2 => "system.posix_acl_access".into(),
+        3 => "system.posix_acl_default".into(),
+        4 => "trusted.".into(),
+        5 => "trusted.lustre.".into(),
+        6 => "security.".into(),
+        other => format!("#{}.", other),
+    }
+}
+
+/// A single xattr entry read out of an inode's inline xattr region, before its namespace
+/// prefix has been applied to the name.
+pub(crate) struct RawXattr {
+    pub name_index: u8,
+    pub suffix: String,
+    pub value: Vec<u8>,
+}
+
+impl RawXattr {
+    pub(crate) fn full_name(&self) -> String {
+        format!("{}{}", prefix_for_index(self.name_index), self.suffix)
+    }
+}
+
+/// The outcome of looking up a single extended attribute by name on an inode.
+///
+/// Distinct from a plain `Option` so callers can't mistake "this inode references shared
+/// xattrs we don't resolve, so we don't actually know" for a confirmed absence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XattrLookup {
+    /// The attribute was found among the inode's inline entries.
+    Found(Vec<u8>),
+    /// Not found inline, but the inode's header references entries in the image-wide
+    /// shared xattr table (which is not resolved — see [`parse_region`]'s doc comment), so
+    /// the attribute may exist there. Callers must not treat this the same as `Absent`.
+    Unresolved,
+    /// Not found inline, and the inode references no shared xattr table entries either, so
+    /// it genuinely has no such attribute.
+    Absent,
+}
+
+/// Returns the `shared_count` from an inline xattr region's `erofs_xattr_ibody_header`:
+/// the number of entries the inode references in the image-wide shared xattr table.
+pub(crate) fn shared_count(region: &[u8]) -> usize {
+    if region.len() < HEADER_BASE_SIZE {
+        0
+    } else {
+        region[4] as usize
+    }
+}
+
+/// Parses the inline xattr region that immediately follows an inode's fixed header: an
+/// `erofs_xattr_ibody_header` (4-byte `name_filter`, a `shared_count` byte, 7 reserved
+/// bytes, then that many 4-byte shared-xattr indices), followed by a sequence of
+/// 4-byte-aligned entries.
+///
+/// KNOWN GAP: shared xattrs (the `shared_count` indices into the image-wide shared-xattr
+/// table) are skipped over, not resolved, so only attributes stored inline on this inode
+/// are returned. On real-world images (notably Android/container EROFS images, this
+/// feature's whole motivating use case) `security.selinux` and capability xattrs are
+/// commonly deduplicated into that shared table. [`EroFS::get_xattr`](crate::filesystem::EroFS::get_xattr)
+/// uses [`shared_count`] to report [`XattrLookup::Unresolved`] rather than silently
+/// claiming such an attribute is absent; resolving it for real requires reading the
+/// superblock's shared-xattr table, which is not implemented yet.
+pub(crate) fn parse_region(region: &[u8]) -> Result<Vec<RawXattr>> {
+    if region.len() < HEADER_BASE_SIZE {
+        return Ok(Vec::new());
+    }
+
+    let shared_count = region[4] as usize;
+    let mut offset = HEADER_BASE_SIZE + shared_count * 4;
+
+    let mut entries = Vec::new();
+    while offset + ENTRY_HEADER_SIZE <= region.len() {
+        let name_len = region[offset] as usize;
+        let name_index = region[offset + 1];
+        let value_size_buf = region[offset + 2..offset + 4]
+            .try_into()
+            .map_err(|_| Error::CorruptedData("failed to read xattr value size".into()))?;
+        let value_size = u16::from_le_bytes(value_size_buf) as usize;
+
+        let name_start = offset + ENTRY_HEADER_SIZE;
+        let name_end = name_start + name_len;
+        let value_end = name_end + value_size;
+        if value_end > region.len() {
+            break;
+        }
+
+        entries.push(RawXattr {
+            name_index,
+            suffix: String::from_utf8_lossy(&region[name_start..name_end]).into_owned(),
+            value: region[name_end..value_end].to_vec(),
+        });
+
+        let entry_len = ENTRY_HEADER_SIZE + name_len + value_size;
+        offset += entry_len.div_ceil(4) * 4;
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_header(region: &mut Vec<u8>, shared_count: u8) {
+        region.extend_from_slice(&0u32.to_le_bytes()); // name_filter
+        region.push(shared_count);
+        region.extend_from_slice(&[0u8; 7]); // reserved
+    }
+
+    fn push_entry(region: &mut Vec<u8>, name_index: u8, suffix: &[u8], value: &[u8]) {
+        region.push(suffix.len() as u8);
+        region.push(name_index);
+        region.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        region.extend_from_slice(suffix);
+        region.extend_from_slice(value);
+        let entry_len = ENTRY_HEADER_SIZE + suffix.len() + value.len();
+        let padding = entry_len.div_ceil(4) * 4 - entry_len;
+        region.extend(core::iter::repeat(0u8).take(padding));
+    }
+
+    #[test]
+    fn parses_single_entry() {
+        let mut region = Vec::new();
+        push_header(&mut region, 0);
+        push_entry(&mut region, 1, b"test", b"v1");
+
+        let entries = parse_region(&region).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name_index, 1);
+        assert_eq!(entries[0].suffix, "test");
+        assert_eq!(entries[0].value, b"v1");
+        assert_eq!(entries[0].full_name(), "user.test");
+    }
+
+    #[test]
+    fn parses_multiple_entries_across_namespaces() {
+        let mut region = Vec::new();
+        push_header(&mut region, 0);
+        push_entry(&mut region, 6, b"selinux", b"unconfined_u");
+        push_entry(&mut region, 4, b"overlay.opaque", b"y");
+
+        let entries = parse_region(&region).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].full_name(), "security.selinux");
+        assert_eq!(entries[1].full_name(), "trusted.overlay.opaque");
+    }
+
+    #[test]
+    fn region_shorter_than_header_yields_no_entries() {
+        let region = vec![0u8; HEADER_BASE_SIZE - 1];
+        assert_eq!(parse_region(&region).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn truncated_entry_value_is_dropped() {
+        let mut region = Vec::new();
+        push_header(&mut region, 0);
+        push_entry(&mut region, 1, b"test", b"v1");
+        // Chop off the 2 padding bytes plus one value byte so the last entry's value runs
+        // past the end of the region.
+        region.truncate(region.len() - 3);
+
+        assert_eq!(parse_region(&region).unwrap().len(), 0);
+    }
+}